@@ -1,16 +1,21 @@
 //! Universal Android Debloater Next Generation
 //! Robust self-update with retries, timeouts & rate-limit handling (revives #1040)
 
+use bzip2::read::BzDecoder;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
 use flate2::read::GzDecoder;
 use reqwest::{Client, StatusCode};
+use semver::Version;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 use std::fs::{self, File};
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::process;
 use std::time::Duration;
 use tar::Archive;
 use thiserror::Error;
+use xz2::read::XzDecoder;
 
 #[derive(Debug, Error)]
 pub enum UpdateError {
@@ -20,110 +25,634 @@ pub enum UpdateError {
     RateLimited,
     #[error("Download failed: {0}")]
     Download(#[from] reqwest::Error),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
     #[error("Failed to extract update")]
     Extraction,
     #[error("No valid binary found in archive")]
     InvalidBinary,
+    #[error("No release asset found for this platform ({0})")]
+    NoAssetForPlatform(String),
+    #[error("Unsupported archive format: {0}")]
+    UnsupportedArchive(String),
+    #[error("Invalid version string: {0}")]
+    InvalidVersion(String),
+    #[error("No release found on the {0} track")]
+    NoReleaseOnTrack(&'static str),
+    #[error("Checksum mismatch for downloaded release: expected {expected}, got {got}")]
+    ChecksumMismatch { expected: String, got: String },
+    #[error("Release signature verification failed")]
+    InvalidSignature,
+    #[error("Failed to install update: {0}")]
+    ReplaceFailed(String),
 }
 
-async fn perform_self_update() -> Result<(), UpdateError> {
+/// Public key the maintainers sign release checksums with. This is a
+/// placeholder (all zero bytes) until release signing is actually wired up
+/// on the publishing side — `verify_release_signature` treats this exact
+/// value as "no key configured yet" and skips signature verification
+/// entirely, even if a `.sig` asset is present. Swapping in a real key here
+/// is what turns that check on; doing so earlier would reject every real
+/// signature and, since updating is the only way to ship the fix, brick the
+/// update path for every user.
+const RELEASE_SIGNING_PUBKEY: [u8; 32] = [0u8; 32];
+
+/// Which release track the updater checks. GitHub's `/releases/latest`
+/// endpoint only ever returns the newest non-prerelease build, so beta and
+/// nightly testers need to browse the full release list instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReleaseTrack {
+    #[default]
+    Stable,
+    Beta,
+    Nightly,
+}
+
+impl ReleaseTrack {
+    /// The tag-name suffix releases on this track are published under, e.g.
+    /// `v1.4.0-beta`. `None` for `Stable`, which has no suffix convention.
+    fn tag_suffix(self) -> Option<&'static str> {
+        match self {
+            Self::Stable => None,
+            Self::Beta => Some("-beta"),
+            Self::Nightly => Some("-nightly"),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+            Self::Nightly => "nightly",
+        }
+    }
+
+    /// Reads the update channel from the `UADNG_UPDATE_CHANNEL` environment
+    /// variable (`stable`/`beta`/`nightly`, case-insensitive), falling back
+    /// to `Stable` when it's unset or unrecognized. This is the config knob
+    /// beta/nightly testers flip to actually get prereleases offered — the
+    /// `ReleaseTrack` matching logic is otherwise unreachable from `main`.
+    fn from_env() -> Self {
+        match std::env::var("UADNG_UPDATE_CHANNEL") {
+            Ok(channel) if channel.eq_ignore_ascii_case("beta") => Self::Beta,
+            Ok(channel) if channel.eq_ignore_ascii_case("nightly") => Self::Nightly,
+            _ => Self::Stable,
+        }
+    }
+}
+
+/// Parses a release's `tag_name` (minus its leading `v`) as a semver version.
+fn release_version(release: &Value) -> Result<Version, UpdateError> {
+    let tag = release["tag_name"].as_str().unwrap_or("");
+    Version::parse(tag.trim_start_matches('v')).map_err(|_| UpdateError::InvalidVersion(tag.to_string()))
+}
+
+/// The archive container a release asset ships in. Release names dictate the
+/// format, so Windows builds are typically `.zip` while Unix builds favor a
+/// compressed tarball.
+#[derive(Debug, PartialEq, Eq)]
+enum ArchiveKind {
+    TarGz,
+    TarBz2,
+    TarXz,
+    Zip,
+}
+
+impl ArchiveKind {
+    fn from_name(name: &str) -> Result<Self, UpdateError> {
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(Self::TarGz)
+        } else if name.ends_with(".tar.bz2") {
+            Ok(Self::TarBz2)
+        } else if name.ends_with(".tar.xz") {
+            Ok(Self::TarXz)
+        } else if name.ends_with(".zip") {
+            Ok(Self::Zip)
+        } else {
+            Err(UpdateError::UnsupportedArchive(name.to_string()))
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::TarGz => ".tar.gz",
+            Self::TarBz2 => ".tar.bz2",
+            Self::TarXz => ".tar.xz",
+            Self::Zip => ".zip",
+        }
+    }
+}
+
+/// Which stage of the update an [`UpdateProgress`] event describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdatePhase {
+    Downloading,
+    Extracting,
+}
+
+/// A progress snapshot emitted while an update is in flight. In `Downloading`
+/// events `total` is `None` when the server didn't send a `Content-Length`
+/// header; `Extracting` events carry `downloaded: 0` and `total: None`.
+#[derive(Debug, Clone, Copy)]
+pub struct UpdateProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+    pub phase: UpdatePhase,
+}
+
+/// The sink a caller (eventually a GUI) can pass in to receive
+/// [`UpdateProgress`] events as the update downloads and extracts.
+pub type ProgressSender = tokio::sync::mpsc::Sender<UpdateProgress>;
+
+/// Best-effort progress emission: a slow or absent receiver must never stall
+/// the update, so a full channel just drops the event.
+fn report_progress(progress: Option<&ProgressSender>, downloaded: u64, total: Option<u64>, phase: UpdatePhase) {
+    if let Some(tx) = progress {
+        let _ = tx.try_send(UpdateProgress { downloaded, total, phase });
+    }
+}
+
+/// Builds the expected release-asset filename suffix for the host platform,
+/// e.g. `-linux-x86_64` or `-windows-x86_64`. The archive extension is left
+/// out here since it varies by release (tar.gz, tar.bz2, tar.xz, zip).
+fn platform_suffix() -> String {
+    format!("-{}-{}", std::env::consts::OS, std::env::consts::ARCH)
+}
+
+/// Scans the release's `assets` array for the asset matching the host's
+/// OS/architecture, so the updater works on every supported platform instead
+/// of always grabbing `assets[0]`. Returns the asset's filename (used to
+/// detect the archive container) and its download URL.
+fn find_asset(latest: &Value) -> Result<(String, String), UpdateError> {
+    let suffix = platform_suffix();
+    latest["assets"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find_map(|asset| {
+            let name = asset["name"].as_str()?;
+            if !name.contains(&suffix) {
+                return None;
+            }
+            let url = asset["browser_download_url"].as_str()?;
+            Some((name.to_string(), url.to_string()))
+        })
+        .ok_or(UpdateError::NoAssetForPlatform(suffix))
+}
+
+/// Locates a checksum asset for `asset_name`: a dedicated `<asset>.sha256`
+/// file if the release publishes one, otherwise a combined
+/// `SHA256SUMS`/`SHA256SUMS.txt` manifest. Returns the asset's URL and
+/// whether it's a multi-file manifest (`true`) or a single hash (`false`).
+fn find_checksum_asset(latest: &Value, asset_name: &str) -> Option<(String, bool)> {
+    let assets = latest["assets"].as_array()?;
+    let per_file_name = format!("{asset_name}.sha256");
+
+    if let Some(url) = assets.iter().find_map(|a| {
+        (a["name"].as_str()? == per_file_name)
+            .then(|| a["browser_download_url"].as_str())
+            .flatten()
+    }) {
+        return Some((url.to_string(), false));
+    }
+
+    assets
+        .iter()
+        .find_map(|a| {
+            let name = a["name"].as_str()?;
+            (name == "SHA256SUMS" || name == "SHA256SUMS.txt")
+                .then(|| a["browser_download_url"].as_str())
+                .flatten()
+        })
+        .map(|url| (url.to_string(), true))
+}
+
+/// Attempt budget and backoff schedule shared by every retrying network call
+/// in this file.
+const MAX_ATTEMPTS: u32 = 5;
+const BACKOFF_MS: [u64; 5] = [1000, 2000, 3000, 5000, 8000];
+
+fn backoff_for(attempt: u32) -> Duration {
+    Duration::from_millis(BACKOFF_MS[(attempt.saturating_sub(1) as usize).min(BACKOFF_MS.len() - 1)])
+}
+
+/// Sends a GET request, retrying rate-limit responses and transient network
+/// errors on the shared backoff schedule. Returns the first successful (2xx)
+/// response for the caller to read the body from — callers must not read a
+/// non-success response's body as valid data, so this status check has to
+/// happen before any caller touches `.text()`/`.bytes()`/`.json()`.
+async fn get_with_retries(client: &Client, url: &str) -> Result<reqwest::Response, UpdateError> {
+    let mut attempts = 0u32;
+
+    loop {
+        attempts += 1;
+        match client.get(url).send().await {
+            Ok(r) if r.status().is_success() => return Ok(r),
+            Ok(r) if r.status() == StatusCode::TOO_MANY_REQUESTS && attempts < MAX_ATTEMPTS => {}
+            Err(_) if attempts < MAX_ATTEMPTS => {}
+            Err(e) => return Err(UpdateError::Download(e)),
+            _ => return Err(UpdateError::RateLimited),
+        }
+
+        tokio::time::sleep(backoff_for(attempts)).await;
+    }
+}
+
+/// Fetches the expected SHA-256 hex digest for `asset_name`, or `None` when
+/// the release publishes no checksum asset at all (verification is then
+/// skipped rather than blocking the update on older releases).
+async fn fetch_expected_checksum(latest: &Value, asset_name: &str) -> Result<Option<String>, UpdateError> {
+    let Some((url, is_manifest)) = find_checksum_asset(latest, asset_name) else {
+        return Ok(None);
+    };
+
+    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let text = get_with_retries(&client, &url).await?.text().await?;
+
+    let hash = if is_manifest {
+        text.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            let hash = parts.next()?;
+            let file = parts.next()?.trim_start_matches('*');
+            (file == asset_name).then(|| hash.to_string())
+        })
+    } else {
+        text.split_whitespace().next().map(str::to_string)
+    };
+
+    Ok(hash.map(|h| h.to_lowercase()))
+}
+
+fn verify_checksum(expected: &str, got: &str) -> Result<(), UpdateError> {
+    if expected.eq_ignore_ascii_case(got) {
+        Ok(())
+    } else {
+        Err(UpdateError::ChecksumMismatch {
+            expected: expected.to_string(),
+            got: got.to_string(),
+        })
+    }
+}
+
+/// Locates a detached signature asset (`<asset_name>.sig`) for the release.
+fn find_signature_asset(latest: &Value, asset_name: &str) -> Option<String> {
+    let sig_name = format!("{asset_name}.sig");
+    latest["assets"].as_array()?.iter().find_map(|a| {
+        (a["name"].as_str()? == sig_name)
+            .then(|| a["browser_download_url"].as_str())
+            .flatten()
+            .map(str::to_string)
+    })
+}
+
+/// Verifies `signature_bytes` as an Ed25519 signature over `message`.
+fn verify_signature(message: &[u8], signature_bytes: &[u8]) -> bool {
+    let Ok(key) = VerifyingKey::from_bytes(&RELEASE_SIGNING_PUBKEY) else {
+        return false;
+    };
+    let Ok(signature) = Signature::from_slice(signature_bytes) else {
+        return false;
+    };
+    key.verify(message, &signature).is_ok()
+}
+
+/// If the release publishes a `.sig` asset for `asset_name`, verifies it
+/// against the downloaded archive's checksum digest. Releases without a
+/// signature asset are accepted as-is — signing is an optional hardening
+/// layer on top of the mandatory checksum check.
+async fn verify_release_signature(latest: &Value, asset_name: &str, digest_hex: &str) -> Result<(), UpdateError> {
+    if RELEASE_SIGNING_PUBKEY == [0u8; 32] {
+        // No real signing key has been configured yet — signature
+        // verification isn't live, so don't fail closed on releases that
+        // happen to publish a `.sig` asset for some other purpose.
+        return Ok(());
+    }
+
+    let Some(url) = find_signature_asset(latest, asset_name) else {
+        return Ok(());
+    };
+
+    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+    let signature_bytes = get_with_retries(&client, &url).await?.bytes().await?;
+
+    if verify_signature(digest_hex.as_bytes(), &signature_bytes) {
+        Ok(())
+    } else {
+        Err(UpdateError::InvalidSignature)
+    }
+}
+
+async fn perform_self_update(progress: Option<ProgressSender>, track: ReleaseTrack) -> Result<(), UpdateError> {
     const OWNER: &str = "Universal-Debloater-Alliance";
     const REPO: &str = "universal-android-debloater-next-generation";
 
     println!("Checking for updates…");
 
-    let latest = get_latest_release(OWNER, REPO).await?;
-    let current = env!("CARGO_PKG_VERSION");
-    let tag = latest["tag_name"]
-        .as_str()
-        .unwrap_or("")
-        .trim_start_matches('v');
+    let latest = get_latest_release(OWNER, REPO, track).await?;
+    let latest_version = release_version(&latest)?;
+    let current_version = Version::parse(env!("CARGO_PKG_VERSION"))
+        .map_err(|_| UpdateError::InvalidVersion(env!("CARGO_PKG_VERSION").to_string()))?;
 
-    if tag <= current {
+    if latest_version <= current_version {
         return Ok(());
     }
 
-    println!("New version {tag} found — downloading…");
+    println!("New version {latest_version} found — downloading…");
 
-    let asset_url = latest["assets"][0]["browser_download_url"]
-        .as_str()
-        .ok_or(UpdateError::InvalidBinary)?
-        .to_string();
+    let (asset_name, asset_url) = find_asset(&latest)?;
+    let kind = ArchiveKind::from_name(&asset_name)?;
+    let current_exe = std::env::current_exe()?;
+    let staging_path = sibling_path(&current_exe, ".new");
+    let expected_checksum = fetch_expected_checksum(&latest, &asset_name).await?;
+    if expected_checksum.is_none() {
+        eprintln!(
+            "warning: no checksum published for {asset_name}, skipping checksum verification"
+        );
+    }
 
-    let temp_path = std::env::temp_dir().join("uadng-update.tar.gz");
-    download_with_retries(&asset_url, &temp_path).await?;
-    extract_binary(&temp_path, &std::env::current_exe()?.parent().unwrap())?;
-    fs::remove_file(&temp_path).ok();
+    match kind {
+        // zip requires random access to read its central directory, so it
+        // still goes through a temp file rather than the streaming path.
+        ArchiveKind::Zip => {
+            let temp_path = std::env::temp_dir().join(format!("uadng-update{}", kind.extension()));
+            let digest = download_with_retries(&asset_url, &temp_path, progress.as_ref()).await?;
+            if let Some(expected) = &expected_checksum {
+                verify_checksum(expected, &digest)?;
+            }
+            verify_release_signature(&latest, &asset_name, &digest).await?;
+
+            report_progress(progress.as_ref(), 0, None, UpdatePhase::Extracting);
+            extract_binary(&temp_path, &staging_path)?;
+            fs::remove_file(&temp_path).ok();
+        }
+        _ => {
+            let digest = stream_download_and_extract(&asset_url, kind, &staging_path, progress.as_ref()).await?;
+            if let Some(expected) = &expected_checksum {
+                verify_checksum(expected, &digest)?;
+            }
+            verify_release_signature(&latest, &asset_name, &digest).await?;
+        }
+    }
+
+    restrict_permissions_owner(&staging_path)?;
+    atomic_replace_exe(&current_exe, &staging_path)?;
 
     println!("Update successful! Restarting…");
     process::exit(0);
 }
 
-async fn get_latest_release(owner: &str, repo: &str) -> Result<Value, UpdateError> {
+async fn get_latest_release(owner: &str, repo: &str, track: ReleaseTrack) -> Result<Value, UpdateError> {
+    match track {
+        ReleaseTrack::Stable => get_latest_stable_release(owner, repo).await,
+        ReleaseTrack::Beta | ReleaseTrack::Nightly => get_latest_prerelease(owner, repo, track).await,
+    }
+}
+
+async fn get_latest_stable_release(owner: &str, repo: &str) -> Result<Value, UpdateError> {
     let client = Client::builder()
         .timeout(Duration::from_secs(10))
         .user_agent("UADNG-Updater/1.0")
         .build()?;
 
     let url = format!("https://api.github.com/repos/{owner}/{repo}/releases/latest");
+    Ok(get_with_retries(&client, &url).await?.json().await?)
+}
+
+/// Fetches the full release list and picks the highest-semver prerelease
+/// whose tag matches `track`'s suffix convention, since `/releases/latest`
+/// never returns a prerelease.
+async fn get_latest_prerelease(owner: &str, repo: &str, track: ReleaseTrack) -> Result<Value, UpdateError> {
+    let suffix = track
+        .tag_suffix()
+        .expect("Beta and Nightly tracks always have a tag suffix");
+    let client = Client::builder()
+        .timeout(Duration::from_secs(10))
+        .user_agent("UADNG-Updater/1.0")
+        .build()?;
+
+    let url = format!("https://api.github.com/repos/{owner}/{repo}/releases");
+    let releases: Vec<Value> = get_with_retries(&client, &url).await?.json().await?;
+
+    releases
+        .into_iter()
+        .filter(|r| r["prerelease"].as_bool().unwrap_or(false))
+        .filter(|r| r["tag_name"].as_str().is_some_and(|tag| tag.contains(suffix)))
+        .filter_map(|r| release_version(&r).ok().map(|v| (v, r)))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, r)| r)
+        .ok_or(UpdateError::NoReleaseOnTrack(track.label()))
+}
+
+/// Downloads `url` to `path`, returning the SHA-256 hex digest of the bytes
+/// written so the caller can verify it against a published checksum. Retries
+/// both the initial request and a body read that fails partway through —
+/// a connection drop mid-transfer is exactly as transient as one on connect.
+async fn download_with_retries(
+    url: &str,
+    path: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<String, UpdateError> {
+    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
     let mut attempts = 0u32;
-    let max = 5;
 
     loop {
         attempts += 1;
-        match client.get(&url).send().await {
-            Ok(r) if r.status().is_success() => return Ok(r.json().await?),
-            Ok(r) if r.status() == StatusCode::TOO_MANY_REQUESTS => {}
-            Err(_) if attempts < max => {}
-            Err(e) => return Err(UpdateError::Download(e)),
-            _ => return Err(UpdateError::RateLimited),
+        let r = get_with_retries(&client, url).await?;
+
+        match download_body(r, path, progress).await {
+            Ok(digest) => return Ok(digest),
+            Err(UpdateError::Download(e)) if attempts < MAX_ATTEMPTS => {
+                eprintln!("warning: download interrupted ({e}), retrying ({attempts}/{MAX_ATTEMPTS})");
+                tokio::time::sleep(backoff_for(attempts)).await;
+            }
+            Err(e) => return Err(e),
         }
+    }
+}
+
+/// Reads one response body to `path`, hashing as it goes. A single attempt —
+/// callers that want retry-on-interruption wrap this with a fresh request.
+async fn download_body(
+    mut r: reqwest::Response,
+    path: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<String, UpdateError> {
+    let total = r.content_length();
+    let mut downloaded = 0u64;
+    let mut file = File::create(path)?;
+    let mut hasher = Sha256::new();
 
-        let backoff_ms = [1000, 2000, 3000, 5000, 8000][(attempts.saturating_sub(1) as usize).min(4)];
-        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+    while let Some(chunk) = r.chunk().await? {
+        file.write_all(&chunk)?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        report_progress(progress, downloaded, total, UpdatePhase::Downloading);
     }
+    Ok(format!("{:x}", hasher.finalize()))
 }
 
-async fn download_with_retries(url: &str, path: &Path) -> Result<(), UpdateError> {
-    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
+/// How many pending chunks the download side may buffer before `send`
+/// blocks, i.e. the download is throttled to the extractor's pace.
+const STREAM_CHANNEL_CAPACITY: usize = 8;
 
+/// Downloads `url` and feeds each chunk straight into the archive extractor
+/// as it arrives, instead of writing the whole archive to disk first. The
+/// download runs on this async task while the (blocking) decode/unpack work
+/// runs on a blocking task, connected by a bounded channel that backpressures
+/// the network when extraction falls behind.
+///
+/// Returns the SHA-256 hex digest of the downloaded bytes. `out_path` is a
+/// staging file, not the live executable — checksum/signature verification
+/// and the atomic swap into place both happen only after this returns, so a
+/// failed verification never touches the running binary.
+///
+/// If the body read is interrupted partway through, the whole attempt (fresh
+/// request, fresh channel, fresh extractor task) is retried from scratch
+/// rather than resumed — the channel/extractor pair is single-shot and can't
+/// be fed a second time once the first attempt has forwarded it any bytes.
+async fn stream_download_and_extract(
+    url: &str,
+    kind: ArchiveKind,
+    out_path: &Path,
+    progress: Option<&ProgressSender>,
+) -> Result<String, UpdateError> {
+    let client = Client::builder().timeout(Duration::from_secs(10)).build()?;
     let mut attempts = 0u32;
-    let max = 5;
 
     loop {
         attempts += 1;
-        match client.get(url).send().await {
-            Ok(mut r) if r.status().is_success() => {
-                let mut file = File::create(path)?;
-                while let Some(chunk) = r.chunk().await? {
-                    file.write_all(&chunk)?;
-                }
-                return Ok(());
+        let r = get_with_retries(&client, url).await?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<std::io::Result<Vec<u8>>>(STREAM_CHANNEL_CAPACITY);
+        let out_path_for_extractor = out_path.to_path_buf();
+        let extract_progress = progress.cloned();
+
+        let extractor = tokio::task::spawn_blocking(move || {
+            report_progress(extract_progress.as_ref(), 0, None, UpdatePhase::Extracting);
+            extract_stream(kind, rx, &out_path_for_extractor)
+        });
+        let download = stream_download(r, tx, progress).await;
+        let extracted = extractor.await.map_err(|_| UpdateError::Extraction)?;
+
+        match download {
+            Ok(digest) => {
+                extracted?;
+                return Ok(digest);
             }
-            Ok(r) if r.status() == StatusCode::TOO_MANY_REQUESTS => {}
-            Err(_) if attempts < max => {}
-            Err(e) => return Err(UpdateError::Download(e)),
-            _ => return Err(UpdateError::RateLimited),
+            Err(UpdateError::Download(e)) if attempts < MAX_ATTEMPTS => {
+                eprintln!("warning: stream download interrupted ({e}), retrying ({attempts}/{MAX_ATTEMPTS})");
+                tokio::time::sleep(backoff_for(attempts)).await;
+            }
+            Err(e) => return Err(e),
         }
+    }
+}
 
-        let backoff_ms = [1000, 2000, 3000, 5000, 8000][(attempts.saturating_sub(1) as usize).min(4)];
-        tokio::time::sleep(Duration::from_millis(backoff_ms)).await;
+/// Reads one response body, hashing and forwarding each chunk to `tx` for the
+/// extractor to consume as it arrives. A single attempt, same as
+/// `download_body` — `stream_download_and_extract` retries by restarting the
+/// whole channel/extractor pair, not by resuming this function.
+async fn stream_download(
+    mut r: reqwest::Response,
+    tx: tokio::sync::mpsc::Sender<std::io::Result<Vec<u8>>>,
+    progress: Option<&ProgressSender>,
+) -> Result<String, UpdateError> {
+    let total = r.content_length();
+    let mut downloaded = 0u64;
+    let mut hasher = Sha256::new();
+    // Once the extractor finds its target entry it drops the receiver, so
+    // `tx.send` starts failing. Keep reading and hashing the rest of the
+    // response anyway — the checksum published for the asset covers the
+    // whole file, not just the bytes that happened to arrive before the
+    // match.
+    let mut forwarding = true;
+    while let Some(chunk) = r.chunk().await? {
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        report_progress(progress, downloaded, total, UpdatePhase::Downloading);
+        if forwarding && tx.send(Ok(chunk.to_vec())).await.is_err() {
+            forwarding = false;
+        }
     }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Adapts the receiving end of the download channel into a `Read`, so the
+/// existing `GzDecoder`/`tar::Archive` pipeline can consume it without
+/// knowing the bytes are arriving over a channel rather than from a file.
+struct ChannelReader {
+    rx: tokio::sync::mpsc::Receiver<std::io::Result<Vec<u8>>>,
+    buf: std::collections::VecDeque<u8>,
 }
 
-fn extract_binary(archive_path: &Path, target_dir: &Path) -> Result<(), UpdateError> {
+impl Read for ChannelReader {
+    fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+        while self.buf.is_empty() {
+            match self.rx.blocking_recv() {
+                Some(Ok(chunk)) => self.buf.extend(chunk),
+                Some(Err(e)) => return Err(e),
+                None => return Ok(0), // sender dropped: end of stream
+            }
+        }
+
+        let n = out.len().min(self.buf.len());
+        for (slot, byte) in out[..n].iter_mut().zip(self.buf.drain(..n)) {
+            *slot = byte;
+        }
+        Ok(n)
+    }
+}
+
+fn extract_stream(
+    kind: ArchiveKind,
+    rx: tokio::sync::mpsc::Receiver<std::io::Result<Vec<u8>>>,
+    out_path: &Path,
+) -> Result<(), UpdateError> {
+    let reader = ChannelReader {
+        rx,
+        buf: std::collections::VecDeque::new(),
+    };
+
+    match kind {
+        ArchiveKind::TarGz => extract_tar(Archive::new(GzDecoder::new(reader)), out_path),
+        ArchiveKind::TarBz2 => extract_tar(Archive::new(BzDecoder::new(reader)), out_path),
+        ArchiveKind::TarXz => extract_tar(Archive::new(XzDecoder::new(reader)), out_path),
+        ArchiveKind::Zip => unreachable!("zip archives are extracted from a temp file, not streamed"),
+    }
+}
+
+fn extract_binary(archive_path: &Path, out_path: &Path) -> Result<(), UpdateError> {
+    let name = archive_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or_default();
+    let kind = ArchiveKind::from_name(name)?;
     let file = File::open(archive_path).map_err(|_| UpdateError::Extraction)?;
-    let tar = GzDecoder::new(file);
-    let mut archive = Archive::new(tar);
 
+    match kind {
+        ArchiveKind::TarGz => extract_tar(Archive::new(GzDecoder::new(file)), out_path),
+        ArchiveKind::TarBz2 => extract_tar(Archive::new(BzDecoder::new(file)), out_path),
+        ArchiveKind::TarXz => extract_tar(Archive::new(XzDecoder::new(file)), out_path),
+        ArchiveKind::Zip => extract_zip(file, out_path),
+    }
+}
+
+/// True if an archive entry's filename looks like the UADNG binary.
+fn is_target_binary(name: &str) -> bool {
+    name.contains("universal-android-debloater") || name.contains("uadng")
+}
+
+/// Finds the matching entry and writes its contents to `out_path` (a fixed
+/// staging file, not the entry's own name) so the caller can atomically swap
+/// it into place afterwards.
+fn extract_tar<R: Read>(mut archive: Archive<R>, out_path: &Path) -> Result<(), UpdateError> {
     for entry in archive.entries().map_err(|_| UpdateError::Extraction)? {
         let mut entry = entry.map_err(|_| UpdateError::Extraction)?;
         let path = entry.path().map_err(|_| UpdateError::Extraction)?;
         if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-            if name.contains("universal-android-debloater") || name.contains("uadng") {
-                entry
-                    .unpack(target_dir.join(name))
-                    .map_err(|_| UpdateError::Extraction)?;
+            if is_target_binary(name) {
+                let mut out = File::create(out_path).map_err(|_| UpdateError::Extraction)?;
+                std::io::copy(&mut entry, &mut out).map_err(|_| UpdateError::Extraction)?;
                 return Ok(());
             }
         }
@@ -131,12 +660,91 @@ fn extract_binary(archive_path: &Path, target_dir: &Path) -> Result<(), UpdateEr
     Err(UpdateError::InvalidBinary)
 }
 
+fn extract_zip(file: File, out_path: &Path) -> Result<(), UpdateError> {
+    let mut archive = zip::ZipArchive::new(file).map_err(|_| UpdateError::Extraction)?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|_| UpdateError::Extraction)?;
+        let Some(name) = entry.enclosed_name().and_then(|p| p.file_name().map(|n| n.to_os_string()))
+        else {
+            continue;
+        };
+        let name = name.to_string_lossy().into_owned();
+        if is_target_binary(&name) {
+            let mut out = File::create(out_path).map_err(|_| UpdateError::Extraction)?;
+            std::io::copy(&mut entry, &mut out).map_err(|_| UpdateError::Extraction)?;
+            return Ok(());
+        }
+    }
+    Err(UpdateError::InvalidBinary)
+}
+
+/// Builds the sibling staging/backup path for `current_exe`, e.g.
+/// `uadng.new`/`uadng.old` next to `uadng`.
+fn sibling_path(current_exe: &Path, suffix: &str) -> std::path::PathBuf {
+    let mut file_name = current_exe.file_name().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+    current_exe.with_file_name(file_name)
+}
+
+/// Restricts a freshly extracted binary to owner-only read/write/execute.
+/// A no-op on platforms without Unix permission bits.
+fn restrict_permissions_owner(path: &Path) -> Result<(), UpdateError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))
+            .map_err(|e| UpdateError::ReplaceFailed(e.to_string()))?;
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+    }
+    Ok(())
+}
+
+/// Swaps `staging_path` in for the running executable without ever leaving
+/// neither file in place: the current exe is renamed aside first (so a
+/// rename, not an in-place overwrite, is what touches the live binary on
+/// every platform — required on Windows, safe on Unix), then the staged
+/// binary is renamed into the now-free name. If that second rename fails,
+/// the original binary is restored from its backup; if that restore fails
+/// too, the returned error names the path the previous binary was left at.
+fn atomic_replace_exe(current_exe: &Path, staging_path: &Path) -> Result<(), UpdateError> {
+    let backup_path = sibling_path(current_exe, ".old");
+
+    fs::rename(current_exe, &backup_path).map_err(|e| UpdateError::ReplaceFailed(e.to_string()))?;
+
+    if let Err(e) = fs::rename(staging_path, current_exe) {
+        return match fs::rename(&backup_path, current_exe) {
+            Ok(()) => Err(UpdateError::ReplaceFailed(e.to_string())),
+            Err(rollback_err) => Err(UpdateError::ReplaceFailed(format!(
+                "swap failed ({e}) and rollback failed too ({rollback_err}); the previous binary is stranded at {}, restore it manually",
+                backup_path.display()
+            ))),
+        };
+    }
+
+    Ok(())
+}
+
+/// Removes a `.old` backup left behind by a previous update. Called once the
+/// binary has started up successfully, so stale backups don't accumulate next
+/// to the executable.
+fn cleanup_stale_backup(current_exe: &Path) {
+    fs::remove_file(sibling_path(current_exe, ".old")).ok();
+}
+
 #[tokio::main]
 async fn main() {
-    if let Err(e) = perform_self_update().await {
+    if let Err(e) = perform_self_update(None, ReleaseTrack::from_env()).await {
         eprintln!("Self-update failed (continuing anyway): {e}");
     }
 
+    if let Ok(current_exe) = std::env::current_exe() {
+        cleanup_stale_backup(&current_exe);
+    }
+
     println!("Universal Android Debloater Next Generation");
     println!("Version {}", env!("CARGO_PKG_VERSION"));
     println!("Ready to debloat your device!");
@@ -144,3 +752,101 @@ async fn main() {
     std::thread::sleep(std::time::Duration::from_secs(2));
     println!("(Your debloating logic would run here)");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_kind_from_name_matches_known_extensions() {
+        assert_eq!(ArchiveKind::from_name("uadng-linux-x86_64.tar.gz").unwrap(), ArchiveKind::TarGz);
+        assert_eq!(ArchiveKind::from_name("uadng-linux-x86_64.tgz").unwrap(), ArchiveKind::TarGz);
+        assert_eq!(ArchiveKind::from_name("uadng-linux-x86_64.tar.bz2").unwrap(), ArchiveKind::TarBz2);
+        assert_eq!(ArchiveKind::from_name("uadng-linux-x86_64.tar.xz").unwrap(), ArchiveKind::TarXz);
+        assert_eq!(ArchiveKind::from_name("uadng-windows-x86_64.zip").unwrap(), ArchiveKind::Zip);
+    }
+
+    #[test]
+    fn archive_kind_from_name_rejects_unknown_extension() {
+        assert!(matches!(
+            ArchiveKind::from_name("uadng-linux-x86_64.exe"),
+            Err(UpdateError::UnsupportedArchive(_))
+        ));
+    }
+
+    #[test]
+    fn is_target_binary_matches_either_project_name() {
+        assert!(is_target_binary("universal-android-debloater-next-generation"));
+        assert!(is_target_binary("uadng"));
+        assert!(is_target_binary("uadng.exe"));
+        assert!(!is_target_binary("README.md"));
+    }
+
+    /// Regression test for the bug where `extract_tar` stops reading as soon
+    /// as it finds the target binary entry, dropping the `ChannelReader`'s
+    /// receiver before the rest of the archive — and the rest of the HTTP
+    /// response — has been consumed. If the digest were computed only over
+    /// the bytes forwarded before that drop, it would silently diverge from
+    /// the SHA-256 published for the whole asset. This builds an in-memory
+    /// tar.gz with the target binary first and trailing padding after it,
+    /// feeds it through the same channel/extractor pipeline
+    /// `stream_download_and_extract` uses, forwarding chunks with a copy of
+    /// `stream_download`'s hash-then-send loop (`stream_download` itself needs
+    /// a live `reqwest::Response`), and asserts that loop's digest still
+    /// matches a hash of the full archive bytes.
+    #[tokio::test]
+    async fn streaming_digest_covers_the_whole_archive_not_just_the_extracted_entry() {
+        let mut tar_bytes = Vec::new();
+        {
+            let mut builder = tar::Builder::new(&mut tar_bytes);
+            let data = b"pretend binary contents";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, "uadng", &data[..]).unwrap();
+
+            let padding = vec![0u8; 4096];
+            let mut header = tar::Header::new_gnu();
+            header.set_size(padding.len() as u64);
+            header.set_cksum();
+            builder.append_data(&mut header, "padding.bin", &padding[..]).unwrap();
+            builder.finish().unwrap();
+        }
+
+        let mut gz_bytes = Vec::new();
+        {
+            let mut encoder = flate2::write::GzEncoder::new(&mut gz_bytes, flate2::Compression::default());
+            std::io::Write::write_all(&mut encoder, &tar_bytes).unwrap();
+            encoder.finish().unwrap();
+        }
+
+        let expected_digest = {
+            let mut hasher = Sha256::new();
+            hasher.update(&gz_bytes);
+            format!("{:x}", hasher.finalize())
+        };
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<std::io::Result<Vec<u8>>>(STREAM_CHANNEL_CAPACITY);
+        let out_path = std::env::temp_dir().join("uadng-test-streaming-digest.bin");
+        let out_path_for_extractor = out_path.clone();
+        let extractor = tokio::task::spawn_blocking(move || {
+            extract_stream(ArchiveKind::TarGz, rx, &out_path_for_extractor)
+        });
+
+        let mut hasher = Sha256::new();
+        let mut forwarding = true;
+        for chunk in gz_bytes.chunks(7) {
+            hasher.update(chunk);
+            if forwarding && tx.send(Ok(chunk.to_vec())).await.is_err() {
+                forwarding = false;
+            }
+        }
+        drop(tx);
+        let got_digest = format!("{:x}", hasher.finalize());
+
+        extractor.await.unwrap().unwrap();
+        fs::remove_file(&out_path).ok();
+
+        assert_eq!(got_digest, expected_digest);
+    }
+}